@@ -7,8 +7,44 @@ pub struct AppConfig {
 }
 
 impl AppConfig {
-    /// Reads configuration from system preferences (macOS) or CLI arguments (other platforms).
+    /// Reads configuration from system preferences (macOS), or on
+    /// Linux/Windows from CLI arguments layered over the XDG/registry config
+    /// store - CLI args always win since they're how the daemon overrides a
+    /// stale persisted value for one launch.
+    ///
+    /// This is a launch-time-only seed: a CLI arg given at startup keeps
+    /// beating the store for the rest of the process's life if re-read from
+    /// here, so anything that needs the *live* value after startup (the
+    /// preferences watcher, the write-back commands) must call
+    /// [`Self::from_store`] instead, or it'll see a just-written store value
+    /// get immediately reverted back to the stale CLI arg on the next poll.
     pub fn from_preferences() -> Self {
+        #[cfg(target_os = "macos")]
+        {
+            Self::from_store()
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            Self::from_cli_args().merge_over(Self::from_store())
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            Self::from_cli_args().merge_over(Self::from_store())
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+        {
+            Self::from_cli_args()
+        }
+    }
+
+    /// Reads configuration from the persisted store only (system
+    /// preferences on macOS, the XDG/registry config store elsewhere),
+    /// ignoring CLI args. Use this for anything reading the *current* value
+    /// after startup - CLI args only apply to the launch that received them.
+    pub fn from_store() -> Self {
         #[cfg(target_os = "macos")]
         {
             Self {
@@ -19,9 +55,19 @@ impl AppConfig {
             }
         }
 
-        #[cfg(not(target_os = "macos"))]
+        #[cfg(target_os = "linux")]
         {
-            Self::from_cli_args()
+            linux::read_config()
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            windows::read_config()
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+        {
+            Self::default()
         }
     }
 
@@ -55,10 +101,66 @@ impl AppConfig {
         }
     }
 
+    /// Fills in any field `self` is missing from `base` (the persisted
+    /// store). `self` is expected to hold CLI-supplied values, which always
+    /// take priority over what's on disk/in the registry.
+    #[cfg(not(target_os = "macos"))]
+    fn merge_over(self, base: Self) -> Self {
+        Self {
+            token_path: self.token_path.or(base.token_path),
+            secret: self.secret.or(base.secret),
+            server_url: self.server_url.or(base.server_url),
+            debug_mode: self.debug_mode || base.debug_mode,
+        }
+    }
+
     /// Returns true if all required fields are present.
     pub fn is_valid(&self) -> bool {
         self.token_path.is_some() && self.secret.is_some() && self.server_url.is_some()
     }
+
+    /// Persists `serverUrl` back to the preference store the daemon wrote it
+    /// to, so the change survives a restart.
+    #[cfg(target_os = "macos")]
+    pub fn write_server_url(value: &str) -> anyhow::Result<()> {
+        macos::write_string("serverUrl", value)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn write_server_url(value: &str) -> anyhow::Result<()> {
+        linux::write_string("serverUrl", value)
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn write_server_url(value: &str) -> anyhow::Result<()> {
+        windows::write_string("serverUrl", value)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    pub fn write_server_url(_value: &str) -> anyhow::Result<()> {
+        anyhow::bail!("Persisting serverUrl is not yet supported on this platform")
+    }
+
+    /// Persists `devMode` back to the preference store.
+    #[cfg(target_os = "macos")]
+    pub fn write_debug_mode(value: bool) -> anyhow::Result<()> {
+        macos::write_string("devMode", if value { "1" } else { "0" })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn write_debug_mode(value: bool) -> anyhow::Result<()> {
+        linux::write_string("devMode", if value { "1" } else { "0" })
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn write_debug_mode(value: bool) -> anyhow::Result<()> {
+        windows::write_string("devMode", if value { "1" } else { "0" })
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    pub fn write_debug_mode(_value: bool) -> anyhow::Result<()> {
+        anyhow::bail!("Persisting devMode is not yet supported on this platform")
+    }
 }
 
 #[cfg(target_os = "macos")]
@@ -89,4 +191,258 @@ mod macos {
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
             .unwrap_or(false)
     }
+
+    pub fn write_string(key: &str, value: &str) -> anyhow::Result<()> {
+        let output = Command::new("defaults")
+            .args(["write", BUNDLE_ID, key, value])
+            .output()?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "defaults write failed for '{}': {}",
+                key,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
 }
+
+/// Reads/writes a simple `key=value` config file under the XDG config
+/// directory, accounting for Flatpak/Snap/AppImage sandboxes that remap
+/// where a per-app config directory actually lives.
+///
+/// Scope note: this module resolves the config-file *location* against the
+/// host. There's no agent-socket lookup or `PATH`/other `XDG_*` variable
+/// normalization anywhere in this crate yet for it to apply to - add that
+/// normalization alongside whichever future change introduces the first
+/// consumer that shells out or connects to a socket, rather than here.
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use super::AppConfig;
+
+    /// True when running inside a Flatpak sandbox. `/.flatpak-info` only
+    /// exists on the sandboxed side of the bind mount, so its presence is
+    /// the standard way to detect this at runtime.
+    fn is_flatpak() -> bool {
+        std::path::Path::new("/.flatpak-info").exists()
+    }
+
+    /// Resolves the directory the daemon (running unsandboxed on the host)
+    /// writes `openframe/config` under, as seen from inside this process.
+    ///
+    /// - Flatpak remaps `$XDG_CONFIG_HOME` to the app's private
+    ///   `~/.var/app/<id>/config` directory, which the host daemon never
+    ///   writes to - trusting it here would silently never find the host
+    ///   config. `$HOME` itself is left pointing at the real host home
+    ///   directory, so resolve against `$HOME/.config` directly instead.
+    /// - Snap remaps both `$HOME` and `$XDG_CONFIG_HOME` under
+    ///   `~/snap/<name>/<revision>`. Recent snapd exposes the real host home
+    ///   as `$SNAP_REAL_HOME`; prefer that. Older snapd only gives us
+    ///   `$SNAP_USER_DATA`, a per-revision directory that's at least
+    ///   preserved across upgrades, so fall back to it if `$SNAP_REAL_HOME`
+    ///   isn't set.
+    /// - AppImage (`$APPIMAGE` set) doesn't sandbox the filesystem or remap
+    ///   XDG variables, so the plain XDG defaults already resolve against
+    ///   the host and need no special-casing.
+    fn config_dir() -> PathBuf {
+        if is_flatpak() {
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/root".to_string());
+            return PathBuf::from(home).join(".config").join("openframe");
+        }
+
+        if std::env::var("SNAP").is_ok() {
+            if let Ok(real_home) = std::env::var("SNAP_REAL_HOME") {
+                return PathBuf::from(real_home).join(".config").join("openframe");
+            }
+            if let Ok(snap_user_data) = std::env::var("SNAP_USER_DATA") {
+                return PathBuf::from(snap_user_data).join("openframe");
+            }
+        }
+
+        let base = std::env::var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                std::env::var("HOME")
+                    .map(|home| PathBuf::from(home).join(".config"))
+                    .unwrap_or_else(|_| PathBuf::from(".config"))
+            });
+
+        base.join("openframe")
+    }
+
+    fn config_file() -> PathBuf {
+        config_dir().join("config")
+    }
+
+    fn parse(contents: &str) -> HashMap<String, String> {
+        contents
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+            .collect()
+    }
+
+    fn read_all() -> HashMap<String, String> {
+        std::fs::read_to_string(config_file())
+            .map(|contents| parse(&contents))
+            .unwrap_or_default()
+    }
+
+    pub fn read_config() -> AppConfig {
+        let values = read_all();
+        AppConfig {
+            token_path: values.get("openframe-token-path").cloned(),
+            secret: values.get("openframe-secret").cloned(),
+            server_url: values.get("serverUrl").cloned(),
+            debug_mode: values
+                .get("devMode")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        }
+    }
+
+    pub fn write_string(key: &str, value: &str) -> anyhow::Result<()> {
+        let mut values = read_all();
+        values.insert(key.to_string(), value.to_string());
+
+        let path = config_file();
+        std::fs::create_dir_all(config_dir())?;
+
+        let contents: String = values
+            .iter()
+            .map(|(k, v)| format!("{}={}\n", k, v))
+            .collect();
+        std::fs::write(&path, contents)?;
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn parse_reads_key_value_pairs() {
+            let contents = "openframe-token-path=/var/lib/openframe/token\nserverUrl=https://example.com\ndevMode=1\n";
+            let values = parse(contents);
+
+            assert_eq!(values.get("openframe-token-path").map(String::as_str), Some("/var/lib/openframe/token"));
+            assert_eq!(values.get("serverUrl").map(String::as_str), Some("https://example.com"));
+            assert_eq!(values.get("devMode").map(String::as_str), Some("1"));
+        }
+
+        #[test]
+        fn parse_trims_whitespace_around_key_and_value() {
+            let values = parse("  serverUrl  =  https://example.com  \n");
+            assert_eq!(values.get("serverUrl").map(String::as_str), Some("https://example.com"));
+        }
+
+        #[test]
+        fn parse_ignores_lines_without_an_equals_sign() {
+            let values = parse("not-a-key-value-line\nserverUrl=https://example.com\n");
+            assert_eq!(values.len(), 1);
+            assert_eq!(values.get("serverUrl").map(String::as_str), Some("https://example.com"));
+        }
+    }
+}
+
+/// Reads/writes configuration under `HKEY_CURRENT_USER\Software\OpenFrame`.
+#[cfg(target_os = "windows")]
+mod windows {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    use super::AppConfig;
+
+    const SUBKEY: &str = "Software\\OpenFrame";
+
+    fn open_key() -> Option<RegKey> {
+        RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey(SUBKEY)
+            .ok()
+    }
+
+    pub fn read_config() -> AppConfig {
+        let Some(key) = open_key() else {
+            return AppConfig::default();
+        };
+
+        AppConfig {
+            token_path: key.get_value("openframe-token-path").ok(),
+            secret: key.get_value("openframe-secret").ok(),
+            server_url: key.get_value("serverUrl").ok(),
+            debug_mode: key
+                .get_value::<String, _>("devMode")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        }
+    }
+
+    pub fn write_string(key_name: &str, value: &str) -> anyhow::Result<()> {
+        let (key, _) = RegKey::predef(HKEY_CURRENT_USER).create_subkey(SUBKEY)?;
+        key.set_value(key_name, &value)?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_over_prefers_cli_values_over_store_values() {
+        let cli = AppConfig {
+            token_path: Some("/cli/token".to_string()),
+            secret: None,
+            server_url: Some("https://cli.example.com".to_string()),
+            debug_mode: false,
+        };
+        let store = AppConfig {
+            token_path: Some("/store/token".to_string()),
+            secret: Some("store-secret".to_string()),
+            server_url: Some("https://store.example.com".to_string()),
+            debug_mode: true,
+        };
+
+        let merged = cli.merge_over(store);
+
+        assert_eq!(merged.token_path.as_deref(), Some("/cli/token"));
+        assert_eq!(merged.secret.as_deref(), Some("store-secret"));
+        assert_eq!(merged.server_url.as_deref(), Some("https://cli.example.com"));
+    }
+
+    #[test]
+    fn merge_over_falls_back_to_store_when_cli_value_missing() {
+        let cli = AppConfig::default();
+        let store = AppConfig {
+            token_path: Some("/store/token".to_string()),
+            secret: Some("store-secret".to_string()),
+            server_url: Some("https://store.example.com".to_string()),
+            debug_mode: true,
+        };
+
+        let merged = cli.merge_over(store);
+
+        assert_eq!(merged.token_path.as_deref(), Some("/store/token"));
+        assert_eq!(merged.secret.as_deref(), Some("store-secret"));
+        assert_eq!(merged.server_url.as_deref(), Some("https://store.example.com"));
+    }
+
+    #[test]
+    fn merge_over_debug_mode_is_true_if_either_side_is() {
+        let mut cli = AppConfig::default();
+        let mut store = AppConfig::default();
+        store.debug_mode = true;
+        assert!(cli.clone().merge_over(store.clone()).debug_mode);
+
+        cli.debug_mode = true;
+        store.debug_mode = false;
+        assert!(cli.merge_over(store).debug_mode);
+    }
+}
+
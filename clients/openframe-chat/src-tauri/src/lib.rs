@@ -6,10 +6,14 @@ use tauri::{
 };
 
 mod config_reader;
+mod origin_guard;
+mod preferences_watcher;
 mod token_watcher;
 mod token_decryption_service;
+use origin_guard::is_trusted_origin;
+use preferences_watcher::{DEBUG_MODE_CHANGED_EVENT, SERVER_URL_CHANGED_EVENT};
 use token_watcher::{TokenWatcher, TokenState};
-use tauri::State;
+use tauri::{State, WebviewWindow};
 use std::sync::{Arc, Mutex};
 
 pub struct ServerUrlState {
@@ -26,7 +30,18 @@ fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-fn get_token(token_state: State<TokenState>) -> Option<String> {
+fn get_token(
+    window: WebviewWindow,
+    token_state: State<TokenState>,
+    server_url_state: State<ServerUrlState>,
+) -> Option<String> {
+    let server_url = server_url_state.url.lock().unwrap();
+    if !is_trusted_origin(&window, server_url.as_deref()) {
+        println!("[SECURITY] get_token denied for untrusted window origin");
+        return None;
+    }
+    drop(server_url);
+
     let token = token_state.current_token.lock().unwrap();
     if token.is_some() {
         println!("[INFO] Token requested from frontend");
@@ -37,8 +52,13 @@ fn get_token(token_state: State<TokenState>) -> Option<String> {
 }
 
 #[tauri::command]
-fn get_server_url(server_url_state: State<ServerUrlState>) -> Option<String> {
+fn get_server_url(window: WebviewWindow, server_url_state: State<ServerUrlState>) -> Option<String> {
     let url = server_url_state.url.lock().unwrap();
+    if !is_trusted_origin(&window, url.as_deref()) {
+        println!("[SECURITY] get_server_url denied for untrusted window origin");
+        return None;
+    }
+
     if url.is_some() {
         println!("[INFO] Server URL requested from frontend");
     } else {
@@ -54,6 +74,43 @@ fn get_debug_mode(debug_mode_state: State<DebugModeState>) -> bool {
     *enabled
 }
 
+#[tauri::command]
+fn set_server_url(
+    app_handle: tauri::AppHandle,
+    window: WebviewWindow,
+    server_url_state: State<ServerUrlState>,
+    value: String,
+) -> Result<(), String> {
+    let current = server_url_state.url.lock().unwrap();
+    if !is_trusted_origin(&window, current.as_deref()) {
+        println!("[SECURITY] set_server_url denied for untrusted window origin");
+        return Err("Untrusted origin".to_string());
+    }
+    drop(current);
+
+    preferences_watcher::set_server_url(&app_handle, &server_url_state.url, value)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_debug_mode(
+    app_handle: tauri::AppHandle,
+    window: WebviewWindow,
+    server_url_state: State<ServerUrlState>,
+    debug_mode_state: State<DebugModeState>,
+    value: bool,
+) -> Result<(), String> {
+    let url = server_url_state.url.lock().unwrap();
+    if !is_trusted_origin(&window, url.as_deref()) {
+        println!("[SECURITY] set_debug_mode denied for untrusted window origin");
+        return Err("Untrusted origin".to_string());
+    }
+    drop(url);
+
+    preferences_watcher::set_debug_mode(&app_handle, &debug_mode_state.enabled, value)
+        .map_err(|e| e.to_string())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     println!("[INFO] OpenFrame Chat starting...");
@@ -98,23 +155,29 @@ pub fn run() {
             }
             
             // Manage server URL state
-            let url_state = ServerUrlState {
-                url: Arc::new(Mutex::new(server_url_clone.clone()))
-            };
-            app.manage(url_state);
+            let url_mutex = Arc::new(Mutex::new(server_url_clone.clone()));
+            app.manage(ServerUrlState { url: url_mutex.clone() });
 
             if let Some(url) = &server_url_clone {
                 println!("[INFO] Server URL configured: {}", url);
             } else {
                 println!("[WARN] No server URL provided");
             }
+            if let Err(e) = app.emit(SERVER_URL_CHANGED_EVENT, &server_url_clone) {
+                println!("[ERROR] Failed to emit {}: {}", SERVER_URL_CHANGED_EVENT, e);
+            }
 
             // Manage debug mode state
-            let debug_state = DebugModeState {
-                enabled: Arc::new(Mutex::new(debug_mode_clone))
-            };
-            app.manage(debug_state);
+            let debug_mutex = Arc::new(Mutex::new(debug_mode_clone));
+            app.manage(DebugModeState { enabled: debug_mutex.clone() });
             println!("[INFO] Debug mode: {}", debug_mode_clone);
+            if let Err(e) = app.emit(DEBUG_MODE_CHANGED_EVENT, debug_mode_clone) {
+                println!("[ERROR] Failed to emit {}: {}", DEBUG_MODE_CHANGED_EVENT, e);
+            }
+
+            // Live-reload preferences so changes made outside the app (e.g.
+            // the daemon re-writing serverUrl) reach an already-running frontend.
+            preferences_watcher::start(app.handle().clone(), url_mutex, debug_mutex);
 
             // Start token watcher with app handle if parameters were provided
             if let Some((token_path, secret_key)) = token_params {
@@ -208,7 +271,14 @@ pub fn run() {
                 _ => {}
             }
         })
-        .invoke_handler(tauri::generate_handler![greet, get_token, get_server_url, get_debug_mode]);
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            get_token,
+            get_server_url,
+            get_debug_mode,
+            set_server_url,
+            set_debug_mode
+        ]);
     
     builder.build(tauri::generate_context!())
         .expect("error while building tauri application")
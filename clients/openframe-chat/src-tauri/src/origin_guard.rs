@@ -0,0 +1,100 @@
+use tauri::WebviewWindow;
+use url::Url;
+
+/// Returns true if `window`'s current URL is one we trust to hand secrets to
+/// over IPC: the bundled local content served under the `tauri://localhost`
+/// asset protocol (or its `http://localhost` dev-server equivalent), or the
+/// configured `server_url`'s own origin. Any other remote `http(s)` origin
+/// is rejected so a compromised or redirected page loaded into the webview
+/// can't exfiltrate the agent secret.
+pub fn is_trusted_origin(window: &WebviewWindow, server_url: Option<&str>) -> bool {
+    let url = match window.url() {
+        Ok(url) => url,
+        Err(e) => {
+            println!("[SECURITY] Could not determine window URL: {}", e);
+            return false;
+        }
+    };
+
+    match url.scheme() {
+        "tauri" | "ipc" => true,
+        "http" | "https" => {
+            if is_local_host(url.host_str()) {
+                return true;
+            }
+
+            match server_url.and_then(|s| Url::parse(s).ok()) {
+                Some(allowed) => same_origin(&url, &allowed),
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Matches `localhost`/`127.0.0.1`/`[::1]` directly, plus any `*.localhost`
+/// host - Tauri v2's Windows asset protocol serves the bundled frontend from
+/// `tauri.localhost`/`ipc.localhost` rather than plain `localhost`, and that
+/// content is just as trusted as the scheme-based `tauri://`/`ipc://` case
+/// already allowed above.
+fn is_local_host(host: Option<&str>) -> bool {
+    match host {
+        Some("localhost") | Some("127.0.0.1") | Some("[::1]") => true,
+        Some(host) => host.ends_with(".localhost"),
+        None => false,
+    }
+}
+
+fn same_origin(a: &Url, b: &Url) -> bool {
+    a.scheme() == b.scheme()
+        && a.host_str() == b.host_str()
+        && a.port_or_known_default() == b.port_or_known_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_local_host_matches_plain_localhost_forms() {
+        assert!(is_local_host(Some("localhost")));
+        assert!(is_local_host(Some("127.0.0.1")));
+        assert!(is_local_host(Some("[::1]")));
+    }
+
+    #[test]
+    fn is_local_host_matches_tauri_asset_protocol_hosts() {
+        assert!(is_local_host(Some("tauri.localhost")));
+        assert!(is_local_host(Some("ipc.localhost")));
+    }
+
+    #[test]
+    fn is_local_host_rejects_unrelated_or_missing_hosts() {
+        assert!(!is_local_host(Some("evil.com")));
+        assert!(!is_local_host(Some("notlocalhost")));
+        assert!(!is_local_host(None));
+    }
+
+    #[test]
+    fn same_origin_requires_matching_scheme_host_and_port() {
+        let a = Url::parse("https://app.openframe.com:8443/foo").unwrap();
+        let b = Url::parse("https://app.openframe.com:8443/bar").unwrap();
+        assert!(same_origin(&a, &b));
+
+        let different_host = Url::parse("https://evil.com:8443/foo").unwrap();
+        assert!(!same_origin(&a, &different_host));
+
+        let different_scheme = Url::parse("http://app.openframe.com:8443/foo").unwrap();
+        assert!(!same_origin(&a, &different_scheme));
+
+        let different_port = Url::parse("https://app.openframe.com:9443/foo").unwrap();
+        assert!(!same_origin(&a, &different_port));
+    }
+
+    #[test]
+    fn same_origin_uses_known_default_port_when_unspecified() {
+        let explicit = Url::parse("https://app.openframe.com:443/foo").unwrap();
+        let implicit = Url::parse("https://app.openframe.com/bar").unwrap();
+        assert!(same_origin(&explicit, &implicit));
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::config_reader::AppConfig;
+
+/// Emitted whenever the live-reloaded server URL changes, so the frontend
+/// doesn't need to poll `get_server_url`.
+pub const SERVER_URL_CHANGED_EVENT: &str = "server-url-changed";
+/// Emitted whenever the live-reloaded debug mode flag changes, so the
+/// frontend doesn't need to poll `get_debug_mode`.
+pub const DEBUG_MODE_CHANGED_EVENT: &str = "debug-mode-changed";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Periodically re-reads the persisted preferences store (CFPreferences on
+/// macOS, the XDG/registry store elsewhere - never the launch-time CLI args)
+/// and pushes any change into `ServerUrlState`/`DebugModeState`, emitting the
+/// corresponding event so an already-running frontend picks up changes made
+/// outside the app (e.g. the daemon re-writing `serverUrl`, or this app's own
+/// `set_server_url`/`set_debug_mode`) without needing a restart.
+///
+/// Deliberately reads [`AppConfig::from_store`], not [`AppConfig::from_preferences`]:
+/// on non-macOS, `from_preferences` re-applies the original CLI args over the
+/// store on every call, so a value just written by `set_server_url` would be
+/// seen as reverted back to the stale CLI value on the very next poll.
+pub fn start(app_handle: AppHandle, server_url_state: Arc<Mutex<Option<String>>>, debug_mode_state: Arc<Mutex<bool>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let config = AppConfig::from_store();
+
+            let url_changed = {
+                let mut current = server_url_state.lock().unwrap();
+                if *current != config.server_url {
+                    *current = config.server_url.clone();
+                    true
+                } else {
+                    false
+                }
+            };
+
+            if url_changed {
+                println!("[INFO] Server URL changed, notifying frontend");
+                if let Err(e) = app_handle.emit(SERVER_URL_CHANGED_EVENT, &config.server_url) {
+                    println!("[ERROR] Failed to emit {}: {}", SERVER_URL_CHANGED_EVENT, e);
+                }
+            }
+
+            let debug_mode_changed = {
+                let mut current = debug_mode_state.lock().unwrap();
+                if *current != config.debug_mode {
+                    *current = config.debug_mode;
+                    true
+                } else {
+                    false
+                }
+            };
+
+            if debug_mode_changed {
+                println!("[INFO] Debug mode changed, notifying frontend");
+                if let Err(e) = app_handle.emit(DEBUG_MODE_CHANGED_EVENT, config.debug_mode) {
+                    println!("[ERROR] Failed to emit {}: {}", DEBUG_MODE_CHANGED_EVENT, e);
+                }
+            }
+        }
+    });
+}
+
+/// Writes `server_url` back to the preference store and updates the shared
+/// state + emits [`SERVER_URL_CHANGED_EVENT`] immediately, without waiting
+/// on the next poll.
+pub fn set_server_url(app_handle: &AppHandle, server_url_state: &Mutex<Option<String>>, value: String) -> anyhow::Result<()> {
+    AppConfig::write_server_url(&value)?;
+    *server_url_state.lock().unwrap() = Some(value.clone());
+    if let Err(e) = app_handle.emit(SERVER_URL_CHANGED_EVENT, Some(value)) {
+        println!("[ERROR] Failed to emit {}: {}", SERVER_URL_CHANGED_EVENT, e);
+    }
+    Ok(())
+}
+
+/// Writes `debug_mode` back to the preference store and updates the shared
+/// state + emits [`DEBUG_MODE_CHANGED_EVENT`] immediately.
+pub fn set_debug_mode(app_handle: &AppHandle, debug_mode_state: &Mutex<bool>, value: bool) -> anyhow::Result<()> {
+    AppConfig::write_debug_mode(value)?;
+    *debug_mode_state.lock().unwrap() = value;
+    if let Err(e) = app_handle.emit(DEBUG_MODE_CHANGED_EVENT, value) {
+        println!("[ERROR] Failed to emit {}: {}", DEBUG_MODE_CHANGED_EVENT, e);
+    }
+    Ok(())
+}
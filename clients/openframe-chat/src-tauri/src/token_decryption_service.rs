@@ -0,0 +1,30 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+/// The daemon writes the token file as `base64(nonce || ciphertext)`,
+/// encrypted with a key derived from the per-session secret it also hands
+/// the chat app over CFPreferences/CLI args.
+pub fn decrypt(ciphertext_b64: &str, secret: &str) -> Option<String> {
+    let raw = STANDARD.decode(ciphertext_b64.trim()).ok()?;
+    if raw.len() < 12 {
+        return None;
+    }
+    let (nonce_bytes, ciphertext) = raw.split_at(12);
+
+    let key_bytes = derive_key(secret);
+    let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
+    let cipher = Aes256Gcm::new(key);
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    let plaintext = cipher.decrypt(nonce, ciphertext).ok()?;
+    String::from_utf8(plaintext).ok()
+}
+
+/// Stretches the secret to a 32-byte AES-256 key via SHA-256.
+fn derive_key(secret: &str) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(secret.as_bytes());
+    hasher.finalize().into()
+}
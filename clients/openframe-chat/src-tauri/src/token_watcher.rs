@@ -0,0 +1,71 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::token_decryption_service;
+
+/// Event emitted to the main window whenever the decrypted token changes,
+/// so the frontend can react immediately instead of polling `get_token`.
+///
+/// Carries no payload: the token itself is never put on the event bus, since
+/// any listener in any webview can `listen()` for it, which would undermine
+/// `get_token`'s origin check. The frontend re-fetches the new value through
+/// that guarded command instead.
+pub const TOKEN_CHANGED_EVENT: &str = "token-changed";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+pub struct TokenState {
+    pub current_token: Arc<Mutex<Option<String>>>,
+}
+
+pub struct TokenWatcher;
+
+impl TokenWatcher {
+    /// Starts polling `token_path` for the daemon-written encrypted token,
+    /// decrypting it with `secret`, and emitting [`TOKEN_CHANGED_EVENT`] to
+    /// `app_handle` whenever the decrypted value changes. Returns the shared
+    /// [`TokenState`] immediately so commands can read the latest value
+    /// without waiting on the first poll.
+    pub fn start(token_path: String, secret: String, app_handle: AppHandle) -> TokenState {
+        let current_token = Arc::new(Mutex::new(None));
+        let state = TokenState {
+            current_token: current_token.clone(),
+        };
+
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                let token = match tokio::fs::read_to_string(&token_path).await {
+                    Ok(contents) => token_decryption_service::decrypt(&contents, &secret),
+                    Err(e) => {
+                        println!("[WARN] Failed to read token file '{}': {}", token_path, e);
+                        None
+                    }
+                };
+
+                let changed = {
+                    let mut current = current_token.lock().unwrap();
+                    if *current != token {
+                        *current = token.clone();
+                        true
+                    } else {
+                        false
+                    }
+                };
+
+                if changed {
+                    println!("[INFO] Token changed, notifying frontend");
+                    if let Err(e) = app_handle.emit_to("main", TOKEN_CHANGED_EVENT, ()) {
+                        println!("[ERROR] Failed to emit {}: {}", TOKEN_CHANGED_EVENT, e);
+                    }
+                }
+            }
+        });
+
+        state
+    }
+}
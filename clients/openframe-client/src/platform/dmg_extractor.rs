@@ -5,8 +5,18 @@ use tracing::{info, warn};
 
 #[cfg(target_os = "macos")]
 use tokio::fs;
+
+use crate::platform::DirectoryManager;
+#[cfg(target_os = "macos")]
+use crate::platform::LoggedCommand;
+#[cfg(target_os = "macos")]
+use crate::platform::user_session::get_console_user;
+#[cfg(target_os = "macos")]
+use std::os::unix::fs::PermissionsExt;
+#[cfg(target_os = "macos")]
+use std::future::Future;
 #[cfg(target_os = "macos")]
-use tokio::process::Command;
+use std::pin::Pin;
 
 #[derive(Clone)]
 pub struct DmgExtractor;
@@ -17,7 +27,14 @@ impl DmgExtractor {
     }
 
     #[cfg(target_os = "macos")]
-    pub async fn extract_all(&self, dmg_bytes: Bytes, target_dir: &Path, source_path: Option<&str>) -> Result<()> {
+    pub async fn extract_all(
+        &self,
+        dmg_bytes: Bytes,
+        target_dir: &Path,
+        source_path: Option<&str>,
+        directory_manager: &DirectoryManager,
+        tool_agent_id: &str,
+    ) -> Result<()> {
         info!("[DMG] extract_all: target_dir={}, source_path={:?}, dmg size={} bytes",
             target_dir.display(), source_path, dmg_bytes.len());
 
@@ -32,7 +49,7 @@ impl DmgExtractor {
             .with_context(|| format!("Failed to write DMG to temp file: {}", dmg_path.display()))?;
         info!("[DMG] DMG written to temp file ({} bytes)", dmg_bytes.len());
 
-        self.mount(&dmg_path, &mount_point).await
+        self.mount(&dmg_path, &mount_point, directory_manager, tool_agent_id).await
             .with_context(|| "Failed to mount DMG")?;
 
         let source = match source_path {
@@ -60,7 +77,7 @@ impl DmgExtractor {
 
         info!("[DMG] Copying: {} -> {}", source.display(), target_dir.display());
 
-        let result = self.copy_recursive(&source, target_dir).await
+        let result = self.copy_recursive(&source, target_dir, directory_manager, tool_agent_id).await
             .with_context(|| format!("Failed to copy from {} to {}", source.display(), target_dir.display()));
 
         match &result {
@@ -68,7 +85,7 @@ impl DmgExtractor {
             Err(e) => warn!("[DMG] Copy failed: {:#}", e),
         }
 
-        if let Err(e) = self.unmount(&mount_point).await {
+        if let Err(e) = self.unmount(&mount_point, directory_manager, tool_agent_id).await {
             warn!("[DMG] Failed to unmount: {:#}", e);
         }
         if let Err(e) = fs::remove_file(&dmg_path).await {
@@ -86,24 +103,36 @@ impl DmgExtractor {
     }
 
     #[cfg(not(target_os = "macos"))]
-    pub async fn extract_all(&self, _dmg_bytes: Bytes, target_dir: &Path, _source_path: Option<&str>) -> Result<()> {
+    pub async fn extract_all(
+        &self,
+        _dmg_bytes: Bytes,
+        target_dir: &Path,
+        _source_path: Option<&str>,
+        _directory_manager: &DirectoryManager,
+        _tool_agent_id: &str,
+    ) -> Result<()> {
         Err(anyhow!("DMG extraction is only supported on macOS. Target: {}", target_dir.display()))
     }
 
     #[cfg(target_os = "macos")]
-    async fn mount(&self, dmg_path: &Path, mount_point: &Path) -> Result<()> {
+    async fn mount(
+        &self,
+        dmg_path: &Path,
+        mount_point: &Path,
+        directory_manager: &DirectoryManager,
+        tool_agent_id: &str,
+    ) -> Result<()> {
         fs::create_dir_all(mount_point).await
             .with_context(|| format!("Failed to create mount point: {}", mount_point.display()))?;
 
         info!("[DMG] Mounting: hdiutil attach -nobrowse -readonly -mountpoint {} {}",
             mount_point.display(), dmg_path.display());
 
-        let output = Command::new("hdiutil")
-            .args(["attach", "-nobrowse", "-readonly", "-mountpoint"])
+        let mut cmd = LoggedCommand::for_tool(directory_manager, tool_agent_id, "hdiutil");
+        cmd.args(["attach", "-nobrowse", "-readonly", "-mountpoint"])
             .arg(mount_point)
-            .arg(dmg_path)
-            .output()
-            .await
+            .arg(dmg_path);
+        let output = cmd.output().await
             .context("Failed to execute hdiutil attach")?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -119,14 +148,17 @@ impl DmgExtractor {
     }
 
     #[cfg(target_os = "macos")]
-    async fn unmount(&self, mount_point: &Path) -> Result<()> {
+    async fn unmount(
+        &self,
+        mount_point: &Path,
+        directory_manager: &DirectoryManager,
+        tool_agent_id: &str,
+    ) -> Result<()> {
         info!("[DMG] Unmounting: hdiutil detach {}", mount_point.display());
 
-        let output = Command::new("hdiutil")
-            .args(["detach", "-quiet"])
-            .arg(mount_point)
-            .output()
-            .await
+        let mut cmd = LoggedCommand::for_tool(directory_manager, tool_agent_id, "hdiutil");
+        cmd.args(["detach", "-quiet"]).arg(mount_point);
+        let output = cmd.output().await
             .context("Failed to execute hdiutil detach")?;
 
         if !output.status.success() {
@@ -140,26 +172,114 @@ impl DmgExtractor {
         Ok(())
     }
 
+    /// Recursively copies `source` into `target` (landing at
+    /// `target/<source's file name>`), preserving Unix mode bits and symlinks,
+    /// then `chown`s the copied tree to the console user so a user-launched
+    /// GUI app isn't left root-owned.
     #[cfg(target_os = "macos")]
-    async fn copy_recursive(&self, source: &Path, target: &Path) -> Result<()> {
-        info!("[DMG] Running: cp -R {} {}", source.display(), target.display());
-
-        let output = Command::new("cp")
-            .args(["-R"])
-            .arg(source)
-            .arg(target)
-            .output()
-            .await
-            .context("Failed to execute cp -R")?;
-
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if !output.status.success() {
-            return Err(anyhow!("cp -R failed (exit {}): {}", output.status, stderr));
+    async fn copy_recursive(
+        &self,
+        source: &Path,
+        target: &Path,
+        directory_manager: &DirectoryManager,
+        tool_agent_id: &str,
+    ) -> Result<()> {
+        let name = source
+            .file_name()
+            .with_context(|| format!("Source path has no file name: {}", source.display()))?;
+        let dest = target.join(name);
+
+        info!("[DMG] Copying recursively: {} -> {}", source.display(), dest.display());
+        Self::copy_entry(source, &dest, None).await?;
+        info!("[DMG] Native copy completed successfully");
+
+        match get_console_user() {
+            Some(user) => {
+                info!("[DMG] Chowning {} to uid {}", dest.display(), user.uid);
+                let mut cmd = LoggedCommand::for_tool(directory_manager, tool_agent_id, "chown");
+                cmd.arg("-R").arg(user.uid.to_string()).arg(&dest);
+                let output = cmd.output().await.context("Failed to execute chown")?;
+                if !output.status.success() {
+                    warn!(
+                        "[DMG] chown -R {} {} failed: {}",
+                        user.uid,
+                        dest.display(),
+                        String::from_utf8_lossy(&output.stderr)
+                    );
+                }
+            }
+            None => warn!("[DMG] No console user at /dev/console; leaving installed tree root-owned"),
         }
 
-        info!("[DMG] cp -R completed successfully");
         Ok(())
     }
+
+    /// Copies a single file, directory (recursively), or symlink from
+    /// `source` to `dest`. `mode` overrides the destination's permission
+    /// bits; `None` preserves the source's mode, normalizing any executable
+    /// file to `0o755`.
+    #[cfg(target_os = "macos")]
+    fn copy_entry<'a>(
+        source: &'a Path,
+        dest: &'a Path,
+        mode: Option<u32>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let metadata = fs::symlink_metadata(source)
+                .await
+                .with_context(|| format!("Failed to stat {}", source.display()))?;
+
+            if metadata.is_symlink() {
+                let link_target = fs::read_link(source)
+                    .await
+                    .with_context(|| format!("Failed to read symlink {}", source.display()))?;
+                if fs::symlink_metadata(dest).await.is_ok() {
+                    let _ = fs::remove_file(dest).await;
+                }
+                return tokio::fs::symlink(&link_target, dest).await.with_context(|| {
+                    format!("Failed to create symlink {} -> {}", dest.display(), link_target.display())
+                });
+            }
+
+            if metadata.is_dir() {
+                fs::create_dir_all(dest)
+                    .await
+                    .with_context(|| format!("Failed to create directory {}", dest.display()))?;
+
+                let mut entries = fs::read_dir(source)
+                    .await
+                    .with_context(|| format!("Failed to read directory {}", source.display()))?;
+                while let Some(entry) = entries
+                    .next_entry()
+                    .await
+                    .with_context(|| format!("Failed to read entry in {}", source.display()))?
+                {
+                    let child_source = entry.path();
+                    let child_dest = dest.join(entry.file_name());
+                    Self::copy_entry(&child_source, &child_dest, None)
+                        .await
+                        .with_context(|| format!("Failed to copy {}", child_source.display()))?;
+                }
+
+                let dir_mode = mode.unwrap_or(metadata.permissions().mode() & 0o777);
+                return fs::set_permissions(dest, std::fs::Permissions::from_mode(dir_mode))
+                    .await
+                    .with_context(|| format!("Failed to set permissions on {}", dest.display()));
+            }
+
+            fs::copy(source, dest)
+                .await
+                .with_context(|| format!("Failed to copy file {} -> {}", source.display(), dest.display()))?;
+
+            let file_mode = mode.unwrap_or_else(|| {
+                let bits = metadata.permissions().mode() & 0o777;
+                if bits & 0o111 != 0 { 0o755 } else { bits }
+            });
+            fs::set_permissions(dest, std::fs::Permissions::from_mode(file_mode))
+                .await
+                .with_context(|| format!("Failed to set permissions on {}", dest.display()))
+        })
+    }
 }
 
 impl Default for DmgExtractor {
@@ -0,0 +1,233 @@
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::{Output, Stdio};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use tokio::fs::File;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufWriter};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::platform::DirectoryManager;
+
+/// Wraps `tokio::process::Command`, persisting the full command line plus
+/// interleaved stdout/stderr to a per-operation log file under
+/// `<tool_dir>/logs/`, so a failed install/uninstall can be diagnosed from a
+/// customer machine without re-running it under `tracing`.
+pub struct LoggedCommand {
+    inner: Command,
+    command_line: String,
+    log_path: PathBuf,
+}
+
+impl LoggedCommand {
+    /// Builds a logged command whose output is written to
+    /// `<log_dir>/<operation_id>-<unix_timestamp>.log`.
+    pub fn new(log_dir: impl Into<PathBuf>, operation_id: &str, program: impl AsRef<OsStr>) -> Self {
+        let program = program.as_ref();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let log_path = log_dir
+            .into()
+            .join(format!("{}-{}.log", operation_id, timestamp));
+
+        Self {
+            inner: Command::new(program),
+            command_line: program.to_string_lossy().into_owned(),
+            log_path,
+        }
+    }
+
+    /// Convenience constructor for the common case of logging a command run
+    /// on behalf of a specific tool, under `<tool_dir>/logs/`.
+    pub fn for_tool(
+        directory_manager: &DirectoryManager,
+        tool_agent_id: &str,
+        program: impl AsRef<OsStr>,
+    ) -> Self {
+        Self::new(directory_manager.get_tool_log_dir(tool_agent_id), tool_agent_id, program)
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        let arg = arg.as_ref();
+        self.command_line.push(' ');
+        self.command_line.push_str(&arg.to_string_lossy());
+        self.inner.arg(arg);
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    async fn open_log(&self) -> Result<BufWriter<File>> {
+        if let Some(parent) = self.log_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create log directory: {}", parent.display()))?;
+        }
+
+        let file = File::create(&self.log_path)
+            .await
+            .with_context(|| format!("Failed to open command log: {}", self.log_path.display()))?;
+
+        let mut writer = BufWriter::new(file);
+        writer
+            .write_all(format!("$ {}\n", self.command_line).as_bytes())
+            .await
+            .ok();
+        Ok(writer)
+    }
+
+    /// Runs the command to completion, draining stdout/stderr into the
+    /// buffered log writer as they arrive and recording the exit status,
+    /// then flushes the log and returns the captured `Output`. Output reaches
+    /// the file when the buffer fills or at that final flush, so a command
+    /// that never exits may leave little or nothing on disk.
+    pub async fn output(mut self) -> Result<Output> {
+        let mut writer = self.open_log().await?;
+        writer
+            .write_all(b"--- output (interleaved, streamed live) ---\n")
+            .await
+            .ok();
+        let writer = Arc::new(Mutex::new(writer));
+
+        let mut child = self
+            .inner
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to spawn: {}", self.command_line))?;
+
+        let stdout_pipe = child.stdout.take().expect("stdout is piped");
+        let stderr_pipe = child.stderr.take().expect("stderr is piped");
+
+        let stdout_task = tokio::spawn(Self::drain_pipe(stdout_pipe, writer.clone()));
+        let stderr_task = tokio::spawn(Self::drain_pipe(stderr_pipe, writer.clone()));
+
+        let (stdout_res, stderr_res) = tokio::join!(stdout_task, stderr_task);
+        let stdout_buf = stdout_res.context("stdout drain task panicked")?.context("Failed to read stdout")?;
+        let stderr_buf = stderr_res.context("stderr drain task panicked")?.context("Failed to read stderr")?;
+
+        let status = child
+            .wait()
+            .await
+            .with_context(|| format!("Failed waiting for: {}", self.command_line))?;
+
+        let mut writer = writer.lock().await;
+        writer
+            .write_all(format!("--- exit status: {} ---\n", status).as_bytes())
+            .await
+            .ok();
+        if let Err(e) = writer.flush().await {
+            warn!("Failed to flush command log {}: {:#}", self.log_path.display(), e);
+        }
+        drop(writer);
+
+        info!("Logged command output to {}", self.log_path.display());
+
+        Ok(Output {
+            status,
+            stdout: stdout_buf,
+            stderr: stderr_buf,
+        })
+    }
+
+    /// Reads `pipe` to EOF in chunks, writing each chunk to `writer` as it
+    /// arrives while also collecting it, so the caller gets both a live log
+    /// and the full buffer `Output` needs.
+    async fn drain_pipe(
+        mut pipe: impl AsyncRead + Unpin,
+        writer: Arc<Mutex<BufWriter<File>>>,
+    ) -> Result<Vec<u8>> {
+        let mut collected = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = pipe.read(&mut buf).await.context("Failed to read from pipe")?;
+            if n == 0 {
+                break;
+            }
+            writer.lock().await.write_all(&buf[..n]).await.ok();
+            collected.extend_from_slice(&buf[..n]);
+        }
+        Ok(collected)
+    }
+
+    /// Spawns a long-running process (e.g. launching a user-facing app) and
+    /// returns the `Child` immediately, while a background task drains its
+    /// stdout/stderr into the log file and flushes it once both pipes close.
+    /// The exit status is not written to this log; waiting on the returned
+    /// `Child` is left to the caller.
+    pub fn spawn(mut self) -> Result<Child> {
+        let mut child = self
+            .inner
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to spawn: {}", self.command_line))?;
+
+        let stdout_pipe = child.stdout.take();
+        let stderr_pipe = child.stderr.take();
+        let command_line = self.command_line.clone();
+        let log_path = self.log_path.clone();
+
+        tokio::spawn(async move {
+            let writer = match self.open_log().await {
+                Ok(w) => Arc::new(Mutex::new(w)),
+                Err(e) => {
+                    warn!("Failed to open command log {}: {:#}", log_path.display(), e);
+                    return;
+                }
+            };
+
+            let mut tasks = Vec::new();
+            if let Some(mut pipe) = stdout_pipe {
+                let writer = writer.clone();
+                tasks.push(tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = pipe.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        writer.lock().await.write_all(&buf[..n]).await.ok();
+                    }
+                }));
+            }
+            if let Some(mut pipe) = stderr_pipe {
+                let writer = writer.clone();
+                tasks.push(tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = pipe.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        writer.lock().await.write_all(&buf[..n]).await.ok();
+                    }
+                }));
+            }
+            for task in tasks {
+                let _ = task.await;
+            }
+
+            let mut writer = writer.lock().await;
+            if let Err(e) = writer.flush().await {
+                warn!("Failed to flush command log {} for '{}': {:#}", log_path.display(), command_line, e);
+            }
+        });
+
+        Ok(child)
+    }
+}
@@ -1,15 +1,20 @@
 #[cfg(target_os = "macos")]
 use anyhow::{Context, Result};
 #[cfg(target_os = "macos")]
-use std::process::Command;
+use tracing::warn;
 
 #[cfg(target_os = "macos")]
 use crate::platform::user_session::get_console_user;
+#[cfg(target_os = "macos")]
+use crate::platform::{DirectoryManager, LoggedCommand, SudoKeepalive};
 
+/// Writes every key/value pair for `bundle_id` under a single kept-alive
+/// sudo session, rather than re-authenticating sudo for each key.
 #[cfg(target_os = "macos")]
-pub fn write<'a>(
+pub async fn write<'a>(
     bundle_id: &str,
     prefs: impl IntoIterator<Item = (&'a str, &'a str)>,
+    directory_manager: &DirectoryManager,
 ) -> Result<()> {
     let user = get_console_user().context("No console user found")?;
     let prefs: Vec<_> = prefs.into_iter().collect();
@@ -18,14 +23,24 @@ pub fn write<'a>(
         return Ok(());
     }
 
+    let _sudo_keepalive = match SudoKeepalive::start(directory_manager, bundle_id).await {
+        Ok(guard) => Some(guard),
+        Err(e) => {
+            warn!("Failed to start sudo keepalive for '{}', proceeding without it: {:#}", bundle_id, e);
+            None
+        }
+    };
+
     for (key, value) in &prefs {
-        let status = Command::new("sudo")
-            .args(["-u", &user.username, "defaults", "write", bundle_id, key, value])
-            .status()
+        let mut cmd = LoggedCommand::for_tool(directory_manager, bundle_id, "sudo");
+        cmd.args(["-u", &user.username, "defaults", "write", bundle_id, key, value]);
+        let output = cmd
+            .output()
+            .await
             .with_context(|| format!("Failed to write preference '{}'", key))?;
 
-        if !status.success() {
-            anyhow::bail!("defaults write failed for '{}': exit {}", key, status);
+        if !output.status.success() {
+            anyhow::bail!("defaults write failed for '{}': exit {}", key, output.status);
         }
     }
 
@@ -33,9 +48,10 @@ pub fn write<'a>(
 }
 
 #[cfg(not(target_os = "macos"))]
-pub fn write<'a>(
+pub async fn write<'a>(
     _bundle_id: &str,
     _prefs: impl IntoIterator<Item = (&'a str, &'a str)>,
+    _directory_manager: &crate::platform::DirectoryManager,
 ) -> anyhow::Result<()> {
     Ok(())
 }
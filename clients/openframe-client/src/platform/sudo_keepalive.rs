@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::platform::{DirectoryManager, LoggedCommand};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps a `sudo` credential alive for the duration of a long-running
+/// install/uninstall flow by periodically refreshing its timestamp in the
+/// background, so a sequence of `sudo` invocations that would otherwise
+/// outlive the cached credential doesn't start silently prompting or
+/// failing partway through.
+pub struct SudoKeepalive {
+    task: JoinHandle<()>,
+}
+
+impl SudoKeepalive {
+    /// Validates sudo access once, then spawns a background task that runs
+    /// `sudo -n -v` every 60s to refresh the cached credential. The refresh
+    /// loop stops when the returned guard is dropped.
+    pub async fn start(directory_manager: &DirectoryManager, tool_agent_id: &str) -> Result<Self> {
+        let mut validate = LoggedCommand::for_tool(directory_manager, tool_agent_id, "sudo");
+        validate.arg("-v");
+        let output = validate.output().await.context("Failed to validate sudo access")?;
+        if !output.status.success() {
+            bail!("sudo validation failed: {}", String::from_utf8_lossy(&output.stderr));
+        }
+
+        let directory_manager = directory_manager.clone();
+        let tool_agent_id = tool_agent_id.to_string();
+
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            interval.tick().await; // first tick fires immediately, skip it
+
+            loop {
+                interval.tick().await;
+
+                let mut refresh = LoggedCommand::for_tool(&directory_manager, &tool_agent_id, "sudo");
+                refresh.args(["-n", "-v"]);
+                match refresh.output().await {
+                    Ok(output) if output.status.success() => info!("Refreshed sudo credential"),
+                    Ok(output) => warn!(
+                        "sudo -n -v refresh failed: {}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ),
+                    Err(e) => warn!("Failed to run sudo -n -v refresh: {:#}", e),
+                }
+            }
+        });
+
+        Ok(Self { task })
+    }
+}
+
+impl Drop for SudoKeepalive {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -1,9 +1,10 @@
 use anyhow::{Context, Result};
 use std::process::Command as StdCommand;
-use std::process::Stdio;
 use tokio::process::Command;
 use tracing::{info, warn};
 
+use crate::platform::{DirectoryManager, LoggedCommand};
+
 #[derive(Debug, Clone)]
 pub struct ConsoleUser {
     pub username: String,
@@ -42,19 +43,21 @@ pub async fn launch_as_user(
     executable: &str,
     args: &[String],
     user: &ConsoleUser,
+    directory_manager: &DirectoryManager,
+    tool_agent_id: &str,
 ) -> Result<tokio::process::Child> {
     if !std::path::Path::new(executable).exists() {
         anyhow::bail!("Executable not found: {}", executable);
     }
 
-    match launch_via_launchctl(executable, args, user.uid).await {
+    match launch_via_launchctl(executable, args, user.uid, directory_manager, tool_agent_id).await {
         Ok(child) => return Ok(child),
         Err(e) => {
             warn!("launchctl asuser failed: {:#}, trying sudo -u", e);
         }
     }
 
-    launch_via_sudo(executable, args, &user.username).await
+    launch_via_sudo(executable, args, &user.username, directory_manager, tool_agent_id).await
 }
 
 pub async fn is_process_running(executable_path: &str) -> bool {
@@ -79,11 +82,13 @@ async fn launch_via_launchctl(
     executable: &str,
     args: &[String],
     uid: u32,
+    directory_manager: &DirectoryManager,
+    tool_agent_id: &str,
 ) -> Result<tokio::process::Child> {
     if let Some(app_path) = extract_app_bundle_path(executable) {
         info!("Launching .app bundle via launchctl asuser: {}", app_path);
 
-        let mut cmd = Command::new("launchctl");
+        let mut cmd = LoggedCommand::for_tool(directory_manager, tool_agent_id, "launchctl");
         cmd.arg("asuser")
             .arg(uid.to_string())
             .arg("open")
@@ -96,8 +101,6 @@ async fn launch_via_launchctl(
         }
 
         let child = cmd
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
             .spawn()
             .with_context(|| format!("launchctl asuser {} open -a {} failed", uid, app_path))?;
 
@@ -108,13 +111,10 @@ async fn launch_via_launchctl(
     // Fallback to launchctl for non-.app executables
     info!("Launching via launchctl asuser {}: {}", uid, executable);
 
-    let child = Command::new("launchctl")
-        .arg("asuser")
-        .arg(uid.to_string())
-        .arg(executable)
-        .args(args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
+    let mut cmd = LoggedCommand::for_tool(directory_manager, tool_agent_id, "launchctl");
+    cmd.arg("asuser").arg(uid.to_string()).arg(executable).args(args);
+
+    let child = cmd
         .spawn()
         .with_context(|| format!("launchctl asuser {} failed", uid))?;
 
@@ -138,16 +138,15 @@ async fn launch_via_sudo(
     executable: &str,
     args: &[String],
     username: &str,
+    directory_manager: &DirectoryManager,
+    tool_agent_id: &str,
 ) -> Result<tokio::process::Child> {
     info!("Launching via sudo -u {}: {}", username, executable);
 
-    let child = Command::new("sudo")
-        .arg("-u")
-        .arg(username)
-        .arg(executable)
-        .args(args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
+    let mut cmd = LoggedCommand::for_tool(directory_manager, tool_agent_id, "sudo");
+    cmd.arg("-u").arg(username).arg(executable).args(args);
+
+    let child = cmd
         .spawn()
         .with_context(|| format!("sudo -u {} failed", username))?;
 
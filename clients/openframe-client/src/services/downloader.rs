@@ -0,0 +1,333 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+use crate::models::download_configuration::{DownloadConfiguration, InstallationType};
+
+/// Reported as a `DownloadConfiguration`'s payload streams in, so a caller
+/// (e.g. a Tauri command) can forward progress to the frontend.
+pub trait DownloadProgress: Send + Sync {
+    fn on_progress(&self, tool_agent_id: &str, downloaded: u64, total: Option<u64>);
+}
+
+/// Fetches `DownloadConfiguration`s for the current OS, downloads the
+/// selected asset, and installs it either as a single binary or by
+/// extracting an archive and pulling `target_file_name` out of it.
+pub struct Downloader {
+    client: reqwest::Client,
+}
+
+impl Downloader {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Fetches the JSON array of `DownloadConfiguration`s from `url`.
+    pub async fn fetch_configurations(&self, url: &str) -> Result<Vec<DownloadConfiguration>> {
+        let configs = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch download configurations from {}", url))?
+            .error_for_status()
+            .with_context(|| format!("Download configurations request to {} failed", url))?
+            .json::<Vec<DownloadConfiguration>>()
+            .await
+            .context("Failed to parse download configurations")?;
+
+        Ok(configs)
+    }
+
+    /// Picks the first configuration matching the current OS, if any.
+    pub fn select_for_current_os(configs: Vec<DownloadConfiguration>) -> Option<DownloadConfiguration> {
+        configs.into_iter().find(|c| c.matches_current_os())
+    }
+
+    /// Downloads `config.link` and installs it under `agent_dir`, returning
+    /// the path of the installed binary/bundle.
+    pub async fn download_and_install(
+        &self,
+        config: &DownloadConfiguration,
+        agent_dir: &Path,
+        tool_agent_id: &str,
+        progress: Option<&dyn DownloadProgress>,
+    ) -> Result<PathBuf> {
+        let download_path = self.download(config, tool_agent_id, progress).await?;
+
+        let installed_path = if config.is_folder_extraction() {
+            self.install_from_archive(&download_path, config, agent_dir).await?
+        } else {
+            self.install_single_binary(&download_path, config, agent_dir).await?
+        };
+
+        if config.installation_type == InstallationType::GuiApp {
+            self.register_gui_app(&installed_path, config).await?;
+        }
+
+        let _ = tokio::fs::remove_file(&download_path).await;
+
+        Ok(installed_path)
+    }
+
+    async fn download(
+        &self,
+        config: &DownloadConfiguration,
+        tool_agent_id: &str,
+        progress: Option<&dyn DownloadProgress>,
+    ) -> Result<PathBuf> {
+        let response = self
+            .client
+            .get(&config.link)
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {}", config.link))?
+            .error_for_status()
+            .with_context(|| format!("Download failed: {}", config.link))?;
+
+        let total = response.content_length();
+        let temp_path = std::env::temp_dir().join(format!("{}-{}", tool_agent_id, config.file_name));
+
+        let mut file = tokio::fs::File::create(&temp_path)
+            .await
+            .with_context(|| format!("Failed to create temp download file: {}", temp_path.display()))?;
+
+        let mut stream = response.bytes_stream();
+        let mut downloaded: u64 = 0;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("Failed to read download chunk")?;
+            file.write_all(&chunk)
+                .await
+                .context("Failed to write download chunk")?;
+            downloaded += chunk.len() as u64;
+
+            if let Some(progress) = progress {
+                progress.on_progress(tool_agent_id, downloaded, total);
+            }
+        }
+        file.flush().await.context("Failed to flush downloaded file")?;
+
+        info!("Downloaded {} ({} bytes) to {}", config.link, downloaded, temp_path.display());
+        Ok(temp_path)
+    }
+
+    /// `target_file_name` is a path inside the archive; extract the whole
+    /// archive to a staging dir and move just that path to `agent_dir`.
+    async fn install_from_archive(
+        &self,
+        archive_path: &Path,
+        config: &DownloadConfiguration,
+        agent_dir: &Path,
+    ) -> Result<PathBuf> {
+        let staging_dir = std::env::temp_dir().join(format!("openframe-staging-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&staging_dir)
+            .await
+            .with_context(|| format!("Failed to create staging dir: {}", staging_dir.display()))?;
+
+        self.extract_archive(archive_path, &staging_dir).await?;
+
+        let extracted = staging_dir.join(&config.target_file_name);
+        let name = Path::new(&config.target_file_name)
+            .file_name()
+            .with_context(|| format!("target_file_name has no file name: {}", config.target_file_name))?;
+        let destination = agent_dir.join(name);
+
+        if destination.is_dir() {
+            let _ = tokio::fs::remove_dir_all(&destination).await;
+        } else if destination.exists() {
+            let _ = tokio::fs::remove_file(&destination).await;
+        }
+
+        tokio::fs::create_dir_all(agent_dir)
+            .await
+            .with_context(|| format!("Failed to create agent dir: {}", agent_dir.display()))?;
+        tokio::fs::rename(&extracted, &destination)
+            .await
+            .with_context(|| format!("Failed to move {} to {}", extracted.display(), destination.display()))?;
+
+        let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+
+        info!("Installed {} from archive to {}", config.target_file_name, destination.display());
+        Ok(destination)
+    }
+
+    async fn install_single_binary(
+        &self,
+        download_path: &Path,
+        config: &DownloadConfiguration,
+        agent_dir: &Path,
+    ) -> Result<PathBuf> {
+        let destination = agent_dir.join(&config.target_file_name);
+        tokio::fs::create_dir_all(agent_dir)
+            .await
+            .with_context(|| format!("Failed to create agent dir: {}", agent_dir.display()))?;
+        tokio::fs::copy(download_path, &destination)
+            .await
+            .with_context(|| format!("Failed to install binary to {}", destination.display()))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&destination, std::fs::Permissions::from_mode(0o755))
+                .await
+                .with_context(|| format!("Failed to mark {} executable", destination.display()))?;
+        }
+
+        info!("Installed single binary to {}", destination.display());
+        Ok(destination)
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    async fn extract_archive(&self, archive_path: &Path, dest: &Path) -> Result<()> {
+        let archive_path = archive_path.to_path_buf();
+        let dest = dest.to_path_buf();
+
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let file = std::fs::File::open(&archive_path)
+                .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
+            let mut zip = zip::ZipArchive::new(file)
+                .with_context(|| format!("Failed to read zip archive: {}", archive_path.display()))?;
+            zip.extract(&dest)
+                .with_context(|| format!("Failed to extract zip archive to {}", dest.display()))
+        })
+        .await
+        .context("Zip extraction task panicked")?
+    }
+
+    #[cfg(target_os = "linux")]
+    async fn extract_archive(&self, archive_path: &Path, dest: &Path) -> Result<()> {
+        let archive_path = archive_path.to_path_buf();
+        let dest = dest.to_path_buf();
+
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let file = std::fs::File::open(&archive_path)
+                .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
+            let decoder = flate2::read::GzDecoder::new(file);
+            let mut archive = tar::Archive::new(decoder);
+            archive
+                .unpack(&dest)
+                .with_context(|| format!("Failed to extract tar.gz archive to {}", dest.display()))
+        })
+        .await
+        .context("Tar extraction task panicked")?
+    }
+
+    /// Path to Launch Services' `lsregister` tool, which actually registers a
+    /// bundle with the OS (unlike `open -R`, which only reveals it in
+    /// Finder and doesn't touch the Launch Services database).
+    #[cfg(target_os = "macos")]
+    const LSREGISTER: &'static str =
+        "/System/Library/Frameworks/CoreServices.framework/Frameworks/LaunchServices.framework/Support/lsregister";
+
+    #[cfg(target_os = "macos")]
+    async fn register_gui_app(&self, installed_path: &Path, config: &DownloadConfiguration) -> Result<()> {
+        let Some(bundle_id) = &config.bundle_id else {
+            return Ok(());
+        };
+
+        info!(
+            "Registering .app bundle {} ({}) with Launch Services",
+            installed_path.display(),
+            bundle_id
+        );
+        let output = tokio::process::Command::new(Self::LSREGISTER)
+            .arg("-f")
+            .arg(installed_path)
+            .output()
+            .await
+            .with_context(|| format!("Failed to register .app bundle: {}", installed_path.display()))?;
+
+        if !output.status.success() {
+            warn!(
+                "lsregister failed for {} ({}): {}",
+                installed_path.display(),
+                bundle_id,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    async fn register_gui_app(&self, _installed_path: &Path, _config: &DownloadConfiguration) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Emitted to the main window as a tool's download progresses, so the
+/// frontend can render a progress bar without polling.
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "download-progress";
+
+#[derive(Clone, serde::Serialize)]
+struct DownloadProgressPayload {
+    tool_agent_id: String,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+/// Forwards [`DownloadProgress`] callbacks to the frontend as
+/// [`DOWNLOAD_PROGRESS_EVENT`]. Targets the `main` window specifically
+/// (`emit_to`, not a broadcast `emit`) since progress is only meaningful to
+/// the window that triggered the install.
+struct TauriDownloadProgress {
+    app_handle: tauri::AppHandle,
+}
+
+impl DownloadProgress for TauriDownloadProgress {
+    fn on_progress(&self, tool_agent_id: &str, downloaded: u64, total: Option<u64>) {
+        let payload = DownloadProgressPayload {
+            tool_agent_id: tool_agent_id.to_string(),
+            downloaded,
+            total,
+        };
+        if let Err(e) = self
+            .app_handle
+            .emit_to("main", DOWNLOAD_PROGRESS_EVENT, payload)
+        {
+            warn!("Failed to emit {}: {}", DOWNLOAD_PROGRESS_EVENT, e);
+        }
+    }
+}
+
+/// Fetches the `DownloadConfiguration`s at `configurations_url`, picks the one
+/// matching the current OS, and downloads/installs it under `agent_dir`,
+/// emitting [`DOWNLOAD_PROGRESS_EVENT`] as it goes. Returns the installed
+/// path as a string (Tauri command results must be serializable).
+#[tauri::command]
+pub async fn install_tool_agent(
+    app_handle: tauri::AppHandle,
+    configurations_url: String,
+    agent_dir: String,
+    tool_agent_id: String,
+) -> Result<String, String> {
+    let downloader = Downloader::new();
+
+    let configs = downloader
+        .fetch_configurations(&configurations_url)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let config = Downloader::select_for_current_os(configs)
+        .ok_or_else(|| "No download configuration available for this OS".to_string())?;
+
+    let progress = TauriDownloadProgress { app_handle };
+    let installed_path = downloader
+        .download_and_install(&config, Path::new(&agent_dir), &tool_agent_id, Some(&progress))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(installed_path.display().to_string())
+}
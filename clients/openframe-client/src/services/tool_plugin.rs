@@ -0,0 +1,485 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use tracing::{info, warn};
+
+use crate::models::download_configuration::InstallationType;
+use crate::models::tool_installation_message::ToolInstallationMessage;
+use crate::models::InstalledTool;
+use crate::platform::{DirectoryManager, DmgExtractor, LoggedCommand};
+use crate::services::{ToolCommandParamsResolver, ToolKillService};
+
+/// Per-operation context threaded through a plugin's lifecycle calls, so each
+/// step can attribute its log lines to the tool it's acting on (and, later,
+/// to the on-disk log file a `LoggedCommand` writes for that operation).
+#[derive(Debug, Clone)]
+pub struct OperationLog {
+    pub tool_agent_id: String,
+}
+
+impl OperationLog {
+    pub fn new(tool_agent_id: impl Into<String>) -> Self {
+        Self {
+            tool_agent_id: tool_agent_id.into(),
+        }
+    }
+}
+
+/// A tool-type-specific plugin, modeled on a package-manager plugin lifecycle:
+/// `prepare` runs before any install/remove work, `install`/`remove` perform the
+/// actual work, `finalize` runs after, and `list`/`version` answer inventory
+/// questions. Each implementation encapsulates whatever is special about its
+/// tool type, so orchestrators like `ToolUninstallService` stay generic and new
+/// tool types are added by implementing this trait rather than editing
+/// uninstall logic.
+#[async_trait]
+pub trait ToolPlugin: Send + Sync {
+    /// Runs once before `install`/`remove`. Default: no-op.
+    async fn prepare(&self, log: &OperationLog) -> Result<()> {
+        let _ = log;
+        Ok(())
+    }
+
+    async fn install(&self, msg: &ToolInstallationMessage, log: &OperationLog) -> Result<()>;
+
+    async fn remove(&self, tool: &InstalledTool, log: &OperationLog) -> Result<()>;
+
+    /// Runs once after `install`/`remove` succeeds. Default: no-op.
+    async fn finalize(&self, log: &OperationLog) -> Result<()> {
+        let _ = log;
+        Ok(())
+    }
+
+    /// Lists tools this plugin knows about beyond what's tracked in the
+    /// installed-tools store. Default: none.
+    async fn list(&self, log: &OperationLog) -> Result<Vec<InstalledTool>> {
+        let _ = log;
+        Ok(Vec::new())
+    }
+
+    async fn version(&self, tool: &InstalledTool, log: &OperationLog) -> Result<String>;
+}
+
+/// Plugins keyed by `tool_type`, looked up by orchestrators before falling
+/// back to [`DefaultPlugin`].
+pub type ToolPluginRegistry = HashMap<String, Arc<dyn ToolPlugin>>;
+
+/// Generic "run uninstall command then remove files" behavior, preserved as
+/// the fallback for tool types without a dedicated plugin.
+pub struct DefaultPlugin {
+    command_params_resolver: ToolCommandParamsResolver,
+    directory_manager: DirectoryManager,
+}
+
+impl DefaultPlugin {
+    pub fn new(
+        command_params_resolver: ToolCommandParamsResolver,
+        directory_manager: DirectoryManager,
+    ) -> Self {
+        Self {
+            command_params_resolver,
+            directory_manager,
+        }
+    }
+
+    pub(crate) async fn run_uninstall_command(&self, tool: &InstalledTool) -> Result<()> {
+        let tool_agent_id = &tool.tool_agent_id;
+
+        let uninstall_args = match &tool.uninstallation_command_args {
+            Some(args) if !args.is_empty() => args,
+            _ => {
+                info!("No uninstallation command provided for tool: {}", tool_agent_id);
+                return Ok(());
+            }
+        };
+
+        let processed_args = self
+            .command_params_resolver
+            .process(tool_agent_id, uninstall_args.clone())
+            .context("Failed to process uninstallation command parameters")?;
+
+        let agent_path = self
+            .directory_manager
+            .get_tool_executable_path(tool_agent_id, tool.executable_path.as_deref());
+
+        if !agent_path.exists() {
+            warn!(
+                "Tool agent executable not found at {}, skipping uninstallation command",
+                agent_path.display()
+            );
+            return Ok(());
+        }
+
+        info!("Running uninstallation command for tool: {}", tool_agent_id);
+
+        let mut cmd = LoggedCommand::for_tool(&self.directory_manager, tool_agent_id, &agent_path);
+        cmd.args(&processed_args);
+        let output = cmd
+            .output()
+            .await
+            .context("Failed to execute uninstallation command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            return Err(anyhow::anyhow!(
+                "Uninstallation command for {} exited with status: {}\nstdout: {}\nstderr: {}",
+                tool_agent_id,
+                output.status,
+                stdout,
+                stderr
+            ));
+        }
+
+        info!("Uninstallation command executed successfully for tool: {}", tool_agent_id);
+        Ok(())
+    }
+
+    pub(crate) async fn run_install_command(&self, msg: &ToolInstallationMessage) -> Result<()> {
+        let tool_agent_id = &msg.tool_agent_id;
+
+        let install_args = match &msg.installation_command_args {
+            Some(args) if !args.is_empty() => args,
+            _ => {
+                info!("No installation command provided for tool: {}", tool_agent_id);
+                return Ok(());
+            }
+        };
+
+        let processed_args = self
+            .command_params_resolver
+            .process(tool_agent_id, install_args.clone())
+            .context("Failed to process installation command parameters")?;
+
+        let agent_path = self.directory_manager.get_tool_executable_path(tool_agent_id, None);
+
+        if !agent_path.exists() {
+            return Err(anyhow::anyhow!(
+                "Tool agent executable not found at {}, cannot run installation command",
+                agent_path.display()
+            ));
+        }
+
+        info!("Running installation command for tool: {}", tool_agent_id);
+
+        let mut cmd = LoggedCommand::for_tool(&self.directory_manager, tool_agent_id, &agent_path);
+        cmd.args(&processed_args);
+        let output = cmd
+            .output()
+            .await
+            .context("Failed to execute installation command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            return Err(anyhow::anyhow!(
+                "Installation command for {} exited with status: {}\nstdout: {}\nstderr: {}",
+                tool_agent_id,
+                output.status,
+                stdout,
+                stderr
+            ));
+        }
+
+        info!("Installation command executed successfully for tool: {}", tool_agent_id);
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub(crate) async fn cleanup_gui_app_bundle(&self, tool: &InstalledTool) {
+        if tool.installation_type != InstallationType::GuiApp {
+            return;
+        }
+        let Some(exec_path) = &tool.executable_path else { return };
+
+        let path = std::path::PathBuf::from(exec_path);
+        let Some(app_bundle) = path
+            .ancestors()
+            .find(|p| p.extension().map_or(false, |ext| ext == "app"))
+        else {
+            warn!("Could not find .app bundle in path: {}", exec_path);
+            return;
+        };
+
+        if !app_bundle.exists() {
+            info!("App bundle already removed: {}", app_bundle.display());
+            return;
+        }
+
+        info!("Removing .app bundle: {}", app_bundle.display());
+        if let Err(e) = tokio::fs::remove_dir_all(app_bundle).await {
+            warn!("Failed to remove .app bundle {}: {:#}", app_bundle.display(), e);
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub(crate) async fn cleanup_gui_app_bundle(&self, _tool: &InstalledTool) {}
+}
+
+#[async_trait]
+impl ToolPlugin for DefaultPlugin {
+    async fn install(&self, msg: &ToolInstallationMessage, log: &OperationLog) -> Result<()> {
+        info!("[{}] Running default install for {}", log.tool_agent_id, msg.tool_agent_id);
+        self.run_install_command(msg).await
+    }
+
+    async fn remove(&self, tool: &InstalledTool, log: &OperationLog) -> Result<()> {
+        info!("[{}] Running default uninstall for {}", log.tool_agent_id, tool.tool_agent_id);
+        self.run_uninstall_command(tool).await?;
+        self.cleanup_gui_app_bundle(tool).await;
+        Ok(())
+    }
+
+    async fn version(&self, tool: &InstalledTool, _log: &OperationLog) -> Result<String> {
+        Ok(tool.version.clone())
+    }
+}
+
+/// Whether `tool` is a Fleet install, and so needs [`FleetPlugin`]'s extra
+/// `osqueryd` teardown. Matches on `tool_type` but also falls back to a
+/// case-insensitive substring check on `tool_agent_id`, since older/synced
+/// installs may predate `tool_type` being populated consistently.
+pub(crate) fn is_fleet_tool(tool_agent_id: &str, tool_type: &str) -> bool {
+    tool_type.eq_ignore_ascii_case("fleet") || tool_agent_id.to_lowercase().contains("fleet")
+}
+
+/// Fleet's agent spawns a child `osqueryd` process that isn't tracked as its
+/// own installed tool, so removing Fleet also has to stop that child.
+pub struct FleetPlugin {
+    default: DefaultPlugin,
+    tool_kill_service: ToolKillService,
+}
+
+impl FleetPlugin {
+    pub fn new(default: DefaultPlugin, tool_kill_service: ToolKillService) -> Self {
+        Self {
+            default,
+            tool_kill_service,
+        }
+    }
+}
+
+#[async_trait]
+impl ToolPlugin for FleetPlugin {
+    async fn install(&self, msg: &ToolInstallationMessage, log: &OperationLog) -> Result<()> {
+        self.default.install(msg, log).await
+    }
+
+    async fn remove(&self, tool: &InstalledTool, log: &OperationLog) -> Result<()> {
+        let tool_agent_id = &tool.tool_agent_id;
+
+        info!("Stopping osqueryd for tool: {}", tool_agent_id);
+        self.tool_kill_service
+            .stop_asset("osqueryd", tool_agent_id)
+            .await
+            .with_context(|| format!("Failed to stop osqueryd for: {}", tool_agent_id))?;
+
+        self.default.run_uninstall_command(tool).await?;
+        self.default.cleanup_gui_app_bundle(tool).await;
+        Ok(())
+    }
+
+    async fn version(&self, tool: &InstalledTool, log: &OperationLog) -> Result<String> {
+        self.default.version(tool, log).await
+    }
+}
+
+/// Tools installed as a `.app` bundle are removed by deleting the bundle
+/// directly rather than invoking an uninstall command.
+pub struct GuiAppPlugin {
+    default: DefaultPlugin,
+}
+
+impl GuiAppPlugin {
+    pub fn new(default: DefaultPlugin) -> Self {
+        Self { default }
+    }
+
+    #[cfg(target_os = "macos")]
+    async fn remove_app_bundle(&self, executable_path: &str) -> Result<()> {
+        let path = std::path::PathBuf::from(executable_path);
+        let app_bundle = path
+            .ancestors()
+            .find(|p| p.extension().map_or(false, |ext| ext == "app"))
+            .with_context(|| format!("Could not find .app bundle in path: {}", executable_path))?;
+
+        if !app_bundle.exists() {
+            info!("App bundle already removed: {}", app_bundle.display());
+            return Ok(());
+        }
+
+        info!("Removing .app bundle: {}", app_bundle.display());
+        tokio::fs::remove_dir_all(app_bundle)
+            .await
+            .with_context(|| format!("Failed to remove .app bundle: {}", app_bundle.display()))
+    }
+}
+
+#[async_trait]
+impl ToolPlugin for GuiAppPlugin {
+    async fn install(&self, msg: &ToolInstallationMessage, log: &OperationLog) -> Result<()> {
+        self.default.install(msg, log).await
+    }
+
+    async fn remove(&self, tool: &InstalledTool, _log: &OperationLog) -> Result<()> {
+        if tool.installation_type != InstallationType::GuiApp {
+            return self.default.run_uninstall_command(tool).await;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let Some(exec_path) = &tool.executable_path else {
+                return Ok(());
+            };
+            self.remove_app_bundle(exec_path).await
+        }
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            Ok(())
+        }
+    }
+
+    async fn version(&self, tool: &InstalledTool, log: &OperationLog) -> Result<String> {
+        self.default.version(tool, log).await
+    }
+}
+
+/// DMG-packaged macOS tools have no uninstall-command equivalent for
+/// install: `install` downloads the tool's `.dmg` asset, mounts it via
+/// [`DmgExtractor`], and copies its payload into the tool's install
+/// directory. `remove`/`version` fall back to the same generic behavior as
+/// [`DefaultPlugin`].
+pub struct DmgPlugin {
+    default: DefaultPlugin,
+    directory_manager: DirectoryManager,
+    dmg_extractor: DmgExtractor,
+}
+
+impl DmgPlugin {
+    pub fn new(
+        default: DefaultPlugin,
+        directory_manager: DirectoryManager,
+        dmg_extractor: DmgExtractor,
+    ) -> Self {
+        Self {
+            default,
+            directory_manager,
+            dmg_extractor,
+        }
+    }
+}
+
+#[async_trait]
+impl ToolPlugin for DmgPlugin {
+    async fn install(&self, msg: &ToolInstallationMessage, log: &OperationLog) -> Result<()> {
+        let tool_agent_id = &msg.tool_agent_id;
+
+        let config = msg
+            .download_configurations
+            .iter()
+            .flatten()
+            .find(|c| c.matches_current_os())
+            .with_context(|| format!("No DMG download configuration available for this OS: {}", tool_agent_id))?;
+
+        info!("[{}] Downloading DMG for tool: {}", log.tool_agent_id, tool_agent_id);
+        let dmg_bytes = reqwest::get(&config.link)
+            .await
+            .with_context(|| format!("Failed to download DMG: {}", config.link))?
+            .error_for_status()
+            .with_context(|| format!("DMG download failed: {}", config.link))?
+            .bytes()
+            .await
+            .context("Failed to read DMG response body")?;
+
+        let target_dir = self
+            .directory_manager
+            .get_tool_executable_path(tool_agent_id, None)
+            .parent()
+            .map(|p| p.to_path_buf())
+            .with_context(|| "Tool executable path has no parent directory")?;
+
+        let source_path = config.is_folder_extraction().then_some(config.target_file_name.as_str());
+
+        self.dmg_extractor
+            .extract_all(dmg_bytes, &target_dir, source_path, &self.directory_manager, tool_agent_id)
+            .await
+            .with_context(|| format!("Failed to extract DMG for tool: {}", tool_agent_id))
+    }
+
+    async fn remove(&self, tool: &InstalledTool, log: &OperationLog) -> Result<()> {
+        info!("[{}] Running default uninstall for DMG-installed tool {}", log.tool_agent_id, tool.tool_agent_id);
+        self.default.run_uninstall_command(tool).await?;
+        self.default.cleanup_gui_app_bundle(tool).await;
+        Ok(())
+    }
+
+    async fn version(&self, tool: &InstalledTool, log: &OperationLog) -> Result<String> {
+        self.default.version(tool, log).await
+    }
+}
+
+/// Registers the built-in plugins. Unknown `tool_type`s fall back to the
+/// returned `DefaultPlugin` rather than failing lookup.
+pub fn build_registry(
+    command_params_resolver: ToolCommandParamsResolver,
+    directory_manager: DirectoryManager,
+    tool_kill_service: ToolKillService,
+) -> (ToolPluginRegistry, Arc<dyn ToolPlugin>) {
+    let default = Arc::new(DefaultPlugin::new(
+        command_params_resolver.clone(),
+        directory_manager.clone(),
+    ));
+
+    let mut registry: ToolPluginRegistry = HashMap::new();
+    registry.insert(
+        "fleet".to_string(),
+        Arc::new(FleetPlugin::new(
+            DefaultPlugin::new(command_params_resolver.clone(), directory_manager.clone()),
+            tool_kill_service,
+        )),
+    );
+    registry.insert(
+        "gui_app".to_string(),
+        Arc::new(GuiAppPlugin::new(DefaultPlugin::new(
+            command_params_resolver.clone(),
+            directory_manager.clone(),
+        ))),
+    );
+    registry.insert(
+        "dmg".to_string(),
+        Arc::new(DmgPlugin::new(
+            DefaultPlugin::new(command_params_resolver, directory_manager.clone()),
+            directory_manager,
+            DmgExtractor::new(),
+        )),
+    );
+
+    (registry, default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_fleet_tool_matches_tool_type_case_insensitively() {
+        assert!(is_fleet_tool("agent-1", "Fleet"));
+        assert!(is_fleet_tool("agent-1", "FLEET"));
+        assert!(is_fleet_tool("agent-1", "fleet"));
+    }
+
+    #[test]
+    fn is_fleet_tool_falls_back_to_agent_id_substring() {
+        assert!(is_fleet_tool("MyFleetAgent", "unknown"));
+        assert!(is_fleet_tool("com.fleetdm.agent", "unknown"));
+    }
+
+    #[test]
+    fn is_fleet_tool_rejects_unrelated_tools() {
+        assert!(!is_fleet_tool("agent-1", "gui_app"));
+        assert!(!is_fleet_tool("nginx-agent", "default"));
+    }
+}
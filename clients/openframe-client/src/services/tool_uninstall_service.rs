@@ -1,24 +1,49 @@
 use anyhow::{Context, Result};
-use tracing::{info, warn, debug};
-use tokio::process::Command;
-use tokio::fs;
-use std::path::PathBuf;
-use crate::models::download_configuration::InstallationType;
+use std::fmt;
+use std::sync::Arc;
+use tracing::{info, warn};
 use crate::services::InstalledToolsService;
 use crate::services::ToolCommandParamsResolver;
 use crate::services::ToolKillService;
-use crate::platform::DirectoryManager;
-#[cfg(target_os = "windows")]
-use crate::platform::file_lock::log_file_lock_info;
-#[allow(unused_imports)]
+use crate::services::tool_plugin::{self, is_fleet_tool, OperationLog, ToolPlugin, ToolPluginRegistry};
+use crate::platform::{DirectoryManager, SudoKeepalive};
 use crate::models::InstalledTool;
 
+const UNINSTALL_SESSION_ID: &str = "uninstall-all";
+
+/// Outcome of a [`ToolUninstallService::uninstall_all_collect`] run: every
+/// tool is attempted independently, so a single stuck tool doesn't prevent
+/// the rest from being reported as succeeded.
+///
+/// Implements [`std::error::Error`] so it can be returned as-is in the `Err`
+/// variant of `uninstall_all_collect` — callers that only care whether the
+/// batch fully succeeded can treat it as an error, while callers that need
+/// to act per-tool can still read `succeeded`/`failed` off of it.
+#[derive(Debug, Default)]
+pub struct UninstallReport {
+    pub succeeded: Vec<String>,
+    pub failed: Vec<(String, anyhow::Error)>,
+}
+
+impl fmt::Display for UninstallReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} succeeded, {} failed", self.succeeded.len(), self.failed.len())?;
+        for (tool_agent_id, err) in &self.failed {
+            writeln!(f, "  - {}: {:#}", tool_agent_id, err)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UninstallReport {}
+
 #[derive(Clone)]
 pub struct ToolUninstallService {
     installed_tools_service: InstalledToolsService,
-    command_params_resolver: ToolCommandParamsResolver,
     tool_kill_service: ToolKillService,
     directory_manager: DirectoryManager,
+    plugins: ToolPluginRegistry,
+    default_plugin: Arc<dyn ToolPlugin>,
 }
 
 impl ToolUninstallService {
@@ -28,14 +53,41 @@ impl ToolUninstallService {
         tool_kill_service: ToolKillService,
         directory_manager: DirectoryManager,
     ) -> Self {
+        let (plugins, default_plugin) = tool_plugin::build_registry(
+            command_params_resolver,
+            directory_manager.clone(),
+            tool_kill_service.clone(),
+        );
+
         Self {
             installed_tools_service,
-            command_params_resolver,
             tool_kill_service,
             directory_manager,
+            plugins,
+            default_plugin,
         }
     }
 
+    /// Looks up the plugin registered for `tool`, falling back to the generic
+    /// [`tool_plugin::DefaultPlugin`] for unrecognized types.
+    ///
+    /// Fleet installs are special-cased ahead of the registry lookup: the
+    /// `"fleet"` entry must be selected whenever [`is_fleet_tool`] says so,
+    /// even if `tool_type` isn't an exact `"fleet"` match, so its `osqueryd`
+    /// child always gets stopped.
+    fn plugin_for(&self, tool: &InstalledTool) -> Arc<dyn ToolPlugin> {
+        if is_fleet_tool(&tool.tool_agent_id, &tool.tool_type) {
+            if let Some(plugin) = self.plugins.get("fleet") {
+                return plugin.clone();
+            }
+        }
+
+        self.plugins
+            .get(&tool.tool_type)
+            .cloned()
+            .unwrap_or_else(|| self.default_plugin.clone())
+    }
+
     /// Uninstall all installed tools by running their uninstallation commands
     /// 
     /// This method will fail immediately if any tool fails to uninstall.
@@ -53,6 +105,8 @@ impl ToolUninstallService {
 
         info!("Found {} installed tools to uninstall", installed_tools.len());
 
+        let _sudo_keepalive = self.start_sudo_keepalive().await;
+
         for tool in installed_tools {
             info!("Processing uninstallation for tool: {}", tool.tool_agent_id);
 
@@ -67,9 +121,68 @@ impl ToolUninstallService {
         Ok(())
     }
 
-    /// Uninstall a single tool by running its uninstallation command
-    /// 
-    /// Fails immediately if any step fails (stop process, run uninstall command, remove files)
+    /// Uninstall all installed tools, attempting every tool independently
+    /// rather than aborting on the first failure.
+    ///
+    /// Unlike [`Self::uninstall_all`], one stuck tool doesn't block cleanup of
+    /// the rest: every tool goes through [`Self::uninstall_tool`] (which stops
+    /// at that tool's first failing step, so a tool whose process can't be
+    /// stopped is not handed to its plugin's `remove`), and the outcome of
+    /// each is recorded into the returned [`UninstallReport`]. Returns `Err`
+    /// after every tool has been attempted if at least one failed, or before
+    /// any tool is attempted if the installed tools can't be listed (reported
+    /// as a single `"<all>"` failure). The `Err` is the same
+    /// `UninstallReport`, so callers get the full `succeeded`/`failed`
+    /// picture either way, not just a formatted message.
+    pub async fn uninstall_all_collect(&self) -> std::result::Result<UninstallReport, UninstallReport> {
+        info!("Starting uninstallation of all installed tools (collect mode)");
+
+        let installed_tools = match self.installed_tools_service.get_all().await {
+            Ok(tools) => tools,
+            Err(e) => {
+                let mut report = UninstallReport::default();
+                report
+                    .failed
+                    .push(("<all>".to_string(), e.context("Failed to retrieve installed tools")));
+                return Err(report);
+            }
+        };
+
+        let mut report = UninstallReport::default();
+        let _sudo_keepalive = self.start_sudo_keepalive().await;
+
+        for tool in installed_tools {
+            info!("Processing uninstallation for tool: {}", tool.tool_agent_id);
+
+            match self.uninstall_tool(&tool).await {
+                Ok(()) => {
+                    info!("Successfully uninstalled tool: {}", tool.tool_agent_id);
+                    report.succeeded.push(tool.tool_agent_id.clone());
+                }
+                Err(e) => {
+                    warn!("Failed to uninstall tool {}: {:#}", tool.tool_agent_id, e);
+                    report.failed.push((tool.tool_agent_id.clone(), e));
+                }
+            }
+        }
+
+        if report.failed.is_empty() {
+            info!("All tools uninstalled successfully");
+            Ok(report)
+        } else {
+            warn!(
+                "{} of {} tools failed to uninstall: {:#}",
+                report.failed.len(),
+                report.succeeded.len() + report.failed.len(),
+                report,
+            );
+            Err(report)
+        }
+    }
+
+    /// Uninstall a single tool by dispatching to its registered `ToolPlugin`.
+    ///
+    /// Fails immediately if any step fails (stop process, `prepare`, `remove`, `finalize`).
     async fn uninstall_tool(&self, tool: &crate::models::InstalledTool) -> Result<()> {
         let tool_agent_id = &tool.tool_agent_id;
 
@@ -78,75 +191,17 @@ impl ToolUninstallService {
         self.stop_tool_process(tool).await
             .with_context(|| format!("Failed to stop tool process for: {}", tool_agent_id))?;
 
-        // TODO: make this stop from fleet orbit side or using asset path
-        // Now it's dirty solution to stop osquery manually
-        if (tool.tool_agent_id.to_lowercase().contains("fleet")) {
-            info!("Stopping osqueryd for tool: {}", tool_agent_id);
-            self.tool_kill_service.stop_asset("osqueryd", tool_agent_id).await
-                .with_context(|| format!("Failed to stop tool process for: {}", tool_agent_id))?;
-            info!("Successfully stopped osqueryd for tool: {}", tool_agent_id);
-        } else {
-            info!("Not stopping osqueryd for tool: {}", tool_agent_id);
-        }
-
-        // Check if uninstallation command is provided
-        let uninstall_args = match &tool.uninstallation_command_args {
-            Some(args) if !args.is_empty() => args,
-            _ => {
-                info!("No uninstallation command provided for tool: {}", tool_agent_id);
-                self.cleanup_gui_app_bundle(tool).await;
-                return Ok(());
-            }
-        };
+        let plugin = self.plugin_for(tool);
+        let log = OperationLog::new(tool_agent_id.clone());
 
-        // Process command parameters (replace placeholders)
-        let processed_args = self.command_params_resolver
-            .process(tool_agent_id, uninstall_args.clone())
-            .context("Failed to process uninstallation command parameters")?;
+        plugin.prepare(&log).await
+            .with_context(|| format!("Failed to prepare uninstall for: {}", tool_agent_id))?;
 
-        debug!("Processed uninstallation args for {}: {:?}", tool_agent_id, processed_args);
+        plugin.remove(tool, &log).await
+            .with_context(|| format!("Failed to uninstall tool: {}", tool_agent_id))?;
 
-        let agent_path = self.directory_manager
-            .get_tool_executable_path(tool_agent_id, tool.executable_path.as_deref());
-
-        if !agent_path.exists() {
-            warn!("Tool agent executable not found at {}, skipping uninstallation command", agent_path.display());
-            return Ok(());
-        }
-
-        info!("Running uninstallation command for tool: {}", tool_agent_id);
-
-        // Execute uninstallation command
-        let mut cmd = Command::new(&agent_path);
-        cmd.args(&processed_args);
-
-        let output = cmd.output().await
-            .map_err(|e| {
-                #[cfg(target_os = "windows")]
-                log_file_lock_info(&e, &agent_path.to_string_lossy(), "execute uninstallation command");
-                e
-            })
-            .context("Failed to execute uninstallation command")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            
-            // Fail immediately if uninstall command returns non-zero exit code
-            return Err(anyhow::anyhow!(
-                "Uninstallation command for {} exited with status: {}\nstdout: {}\nstderr: {}",
-                tool_agent_id,
-                output.status,
-                stdout,
-                stderr
-            ));
-        }
-        
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        info!("Uninstallation command executed successfully for tool: {}\nstdout: {}", tool_agent_id, stdout);
-
-        // Cleanup GUI app bundle if applicable
-        self.cleanup_gui_app_bundle(tool).await;
+        plugin.finalize(&log).await
+            .with_context(|| format!("Failed to finalize uninstall for: {}", tool_agent_id))?;
 
         Ok(())
     }
@@ -155,44 +210,56 @@ impl ToolUninstallService {
         self.tool_kill_service.stop_installed_tool(tool).await
     }
 
-    async fn cleanup_gui_app_bundle(&self, tool: &crate::models::InstalledTool) {
-        if tool.installation_type != InstallationType::GuiApp {
-            return;
+    /// Starts a [`SudoKeepalive`] for the whole batch so tools further down
+    /// the list don't hit an expired sudo timestamp. Best-effort: if sudo
+    /// validation fails (e.g. non-interactive session), the batch proceeds
+    /// without it rather than failing outright.
+    async fn start_sudo_keepalive(&self) -> Option<SudoKeepalive> {
+        match SudoKeepalive::start(&self.directory_manager, UNINSTALL_SESSION_ID).await {
+            Ok(guard) => Some(guard),
+            Err(e) => {
+                warn!("Failed to start sudo keepalive, proceeding without it: {:#}", e);
+                None
+            }
         }
+    }
+}
 
-        #[cfg(target_os = "macos")]
-        {
-            let Some(exec_path) = &tool.executable_path else { return };
-            self.remove_macos_app_bundle(exec_path).await;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        #[cfg(not(target_os = "macos"))]
-        {
-            let _ = tool;
-        }
+    #[test]
+    fn display_reports_succeeded_and_failed_counts() {
+        let report = UninstallReport {
+            succeeded: vec!["agent-a".to_string()],
+            failed: vec![("agent-b".to_string(), anyhow::anyhow!("boom"))],
+        };
+
+        let rendered = report.to_string();
+        assert!(rendered.starts_with("1 succeeded, 1 failed"));
+        assert!(rendered.contains("agent-b: boom"));
     }
 
-    #[cfg(target_os = "macos")]
-    async fn remove_macos_app_bundle(&self, executable_path: &str) {
-        let path = PathBuf::from(executable_path);
-        let Some(app_bundle) = path.ancestors()
-            .find(|p| p.extension().map_or(false, |ext| ext == "app"))
-        else {
-            warn!("Could not find .app bundle in path: {}", executable_path);
-            return;
+    #[test]
+    fn display_lists_every_failed_tool() {
+        let report = UninstallReport {
+            succeeded: vec![],
+            failed: vec![
+                ("agent-a".to_string(), anyhow::anyhow!("first failure")),
+                ("agent-b".to_string(), anyhow::anyhow!("second failure")),
+            ],
         };
 
-        if !app_bundle.exists() {
-            info!("App bundle already removed: {}", app_bundle.display());
-            return;
-        }
+        let rendered = report.to_string();
+        assert!(rendered.contains("agent-a: first failure"));
+        assert!(rendered.contains("agent-b: second failure"));
+    }
 
-        info!("Removing .app bundle: {}", app_bundle.display());
-        if let Err(e) = fs::remove_dir_all(app_bundle).await {
-            warn!("Failed to remove .app bundle {}: {:#}", app_bundle.display(), e);
-        } else {
-            info!("Successfully removed .app bundle: {}", app_bundle.display());
-        }
+    #[test]
+    fn implements_std_error_so_it_can_be_returned_as_err() {
+        let report = UninstallReport::default();
+        let _: &dyn std::error::Error = &report;
     }
 }
 